@@ -1,13 +1,143 @@
-use std::{collections::HashMap, hash::Hash, marker::PhantomData, borrow::Borrow};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+
+use core::{
+    borrow::Borrow,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
 
 use ph::fmph::{GOBuildConf, GOConf, GOFunction};
 
+// `GOFunction::write`/`read` are generic over an `io::Read`/`Write` implementation; under
+// `std` that's `std::io`, under `no-std` it's `core2::io` (built with its `alloc` feature,
+// which is what gives `Vec<u8>`/`&[u8]` their `Write`/`Read` impls without `std`). `ph`
+// itself needs to be built against the matching flavor for this to actually link.
+#[cfg(all(feature = "serde", feature = "std"))]
+use std::io;
+#[cfg(all(feature = "serde", not(feature = "std")))]
+use core2::io;
+
+#[cfg(feature = "serde")]
+fn write_function_bytes(function: &GOFunction) -> Result<Vec<u8>, io::Error> {
+    let mut bytes = Vec::with_capacity(function.write_bytes());
+    function.write(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "serde")]
+fn read_function_bytes(mut bytes: &[u8]) -> Result<GOFunction, io::Error> {
+    GOFunction::read(&mut bytes)
+}
+
+// Seed for the per-slot fingerprint hash, independent of whatever seed
+// `GOFunction` picks internally for the MPHF itself.
+const FINGERPRINT_SEED: u64 = 0x9e3779b97f4a7c15;
+
+// A small FNV-1a variant used only to compute fingerprints: we don't want
+// to pull in a hashing crate just for this, and it doesn't need to be
+// cryptographically strong, just independent of the MPHF's own hash.
+struct FingerprintHasher(u64);
+
+impl FingerprintHasher {
+    fn new(seed: u64) -> Self {
+        FingerprintHasher(seed ^ 0xcbf29ce484222325)
+    }
+}
+
+impl Hasher for FingerprintHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+fn fingerprint_of<Q: Hash + ?Sized>(key: &Q, bits: u8) -> u64 {
+    let mut hasher = FingerprintHasher::new(FINGERPRINT_SEED);
+    key.hash(&mut hasher);
+    hasher.finish() & (u64::MAX >> (64 - bits))
+}
+
+// Packed per-slot fingerprints used to reject keys that were never
+// inserted: `bits` bits of `fingerprint_of` per slot, `bits/8` bytes apart.
+struct Fingerprints {
+    bits: u8,
+    bytes: Vec<u8>,
+}
+
+impl Fingerprints {
+    fn bytes_per_slot(bits: u8) -> usize {
+        bits as usize / 8
+    }
+
+    fn build<K: Hash>(keys: &[K], hasher: &GOFunction, bits: u8) -> Fingerprints {
+        let per_slot = Self::bytes_per_slot(bits);
+        let mut bytes = vec![0u8; keys.len() * per_slot];
+
+        for key in keys {
+            let slot = hasher.get(key).unwrap() as usize;
+            let fingerprint = fingerprint_of(key, bits).to_le_bytes();
+            bytes
+                .get_mut(slot * per_slot..(slot + 1) * per_slot)
+                .expect("hasher returned a slot outside the fingerprint table it was just built for")
+                .copy_from_slice(&fingerprint[..per_slot]);
+        }
+
+        Fingerprints { bits, bytes }
+    }
+
+    // `slot` comes from `GOFunction::get`, which after a deserialization round-trip may be
+    // reconstructed from corrupt or tampered bytes and disagree with this table's length.
+    // Treat anything out of range (or that would overflow computing the range) as "no
+    // match" instead of indexing straight into `self.bytes` and panicking.
+    fn matches<Q: Hash + ?Sized>(&self, slot: usize, key: &Q) -> bool {
+        let per_slot = Self::bytes_per_slot(self.bits);
+
+        let Some(start) = slot.checked_mul(per_slot) else { return false };
+        let Some(end) = start.checked_add(per_slot) else { return false };
+        let Some(stored) = self.bytes.get(start..end) else { return false };
+
+        let mut expected = [0u8; 8];
+        expected[..per_slot].copy_from_slice(stored);
+
+        u64::from_le_bytes(expected) == fingerprint_of(key, self.bits)
+    }
+
+    #[cfg(feature = "serde")]
+    fn as_wire(&self) -> (u8, serde_bytes::ByteBuf) {
+        (self.bits, serde_bytes::ByteBuf::from(self.bytes.clone()))
+    }
+
+    #[cfg(feature = "serde")]
+    fn from_wire(bits: u8, bytes: serde_bytes::ByteBuf) -> Fingerprints {
+        Fingerprints { bits, bytes: bytes.into_vec() }
+    }
+}
+
 pub struct PerfectMap<K, V> {
     function: ph::fmph::GOFunction,
     values: Vec<V>,
+    fingerprints: Option<Fingerprints>,
     spooky: PhantomData<K>,
 }
 
+#[cfg(feature = "std")]
 impl<KEY: Hash + Sync, VALUE: Hash + Sync> PerfectMap<KEY, VALUE> {
     pub fn from_map_invert<U: Into<VALUE>>(map: HashMap<U, KEY>) -> PerfectMap<KEY, VALUE> {
         let (values, keys): (Vec<_>, Vec<_>) = map.into_iter().unzip();
@@ -16,20 +146,39 @@ impl<KEY: Hash + Sync, VALUE: Hash + Sync> PerfectMap<KEY, VALUE> {
     }
 }
 
+#[cfg(feature = "std")]
 impl<K: Hash + Sync, V> PerfectMap<K, V> {
     pub fn from_map<U: Into<V>>(map: HashMap<K, U>) -> PerfectMap<K, V> {
         let (keys, values): (Vec<_>, Vec<_>) = map.into_iter().unzip();
 
         PerfectMap::new(&keys, values)
     }
+}
 
+impl<K: Hash + Sync, V> PerfectMap<K, V> {
     pub fn new<U: Into<V>>(keys: &[K], values: Vec<U>) -> PerfectMap<K, V> {
+        PerfectMapBuilder::default().build(keys, values)
+    }
+
+    // Like `new`, but also stores a `bits`-bit fingerprint per slot so `get`/`contains_key`
+    // can reject keys that were never inserted, rather than false-positive on their slot's
+    // real occupant. `bits` must be 8 or 16; higher means fewer false positives (2^-bits)
+    // for more bytes per key.
+    pub fn new_with_fingerprint<U: Into<V>>(keys: &[K], values: Vec<U>, bits: u8) -> PerfectMap<K, V> {
+        PerfectMapBuilder::default()
+            .fingerprint_bits(bits)
+            .build(keys, values)
+    }
+
+    fn build<U: Into<V>>(
+        keys: &[K],
+        values: Vec<U>,
+        build_conf: GOBuildConf,
+        fingerprint_bits: Option<u8>,
+    ) -> PerfectMap<K, V> {
         assert!(keys.len() == values.len());
 
-        let hasher = GOFunction::from_slice_with_conf(
-            &keys,
-            GOBuildConf::with_lsize(GOConf::default(), 300),
-        );
+        let hasher = GOFunction::from_slice_with_conf(&keys, build_conf);
 
         let map_len = values.len();
         let mut reordered_vals = Vec::with_capacity(map_len);
@@ -43,17 +192,233 @@ impl<K: Hash + Sync, V> PerfectMap<K, V> {
             reordered_vals.set_len(map_len);
         }
 
+        let fingerprints = fingerprint_bits.map(|bits| Fingerprints::build(keys, &hasher, bits));
+
         PerfectMap {
             function: hasher,
             values: reordered_vals,
+            fingerprints,
             spooky: PhantomData,
         }
     }
 
     pub fn get<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + ?Sized  {
-        self.function
-            .get(key)
-            .and_then(|v| self.values.get(v as usize))
+        let slot = self.function.get(key)? as usize;
+
+        if let Some(fingerprints) = &self.fingerprints {
+            if !fingerprints.matches(slot, key) {
+                return None;
+            }
+        }
+
+        self.values.get(slot)
+    }
+
+    // Without fingerprints this can false-positive on a key that was never inserted,
+    // since the MPHF maps any key to some slot.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: Hash + ?Sized {
+        self.get(key).is_some()
+    }
+}
+
+// Tunes the FMPHGO space/time tradeoff `new` otherwise hard-codes. Defaults match `new`.
+pub struct PerfectMapBuilder {
+    relative_level_size: u16,
+    bits_per_seed: Option<u8>,
+    #[cfg(feature = "rayon")]
+    use_multiple_threads: bool,
+    fingerprint_bits: Option<u8>,
+}
+
+impl Default for PerfectMapBuilder {
+    fn default() -> Self {
+        PerfectMapBuilder {
+            relative_level_size: 300,
+            bits_per_seed: None,
+            #[cfg(feature = "rayon")]
+            use_multiple_threads: false,
+            fingerprint_bits: None,
+        }
+    }
+}
+
+impl PerfectMapBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Relative level size: higher trades a larger evaluation structure for faster construction.
+    pub fn relative_level_size(mut self, lsize: u16) -> Self {
+        self.relative_level_size = lsize;
+        self
+    }
+
+    // Bits used per seed; lower minimizes bits-per-key at the cost of construction time.
+    pub fn bits_per_seed(mut self, bits: u8) -> Self {
+        self.bits_per_seed = Some(bits);
+        self
+    }
+
+    #[cfg(feature = "rayon")]
+    pub fn use_multiple_threads(mut self, parallel: bool) -> Self {
+        self.use_multiple_threads = parallel;
+        self
+    }
+
+    pub fn fingerprint_bits(mut self, bits: u8) -> Self {
+        assert!(bits == 8 || bits == 16, "fingerprint bits must be 8 or 16");
+        self.fingerprint_bits = Some(bits);
+        self
+    }
+
+    // Turns the builder's knobs into the `GOBuildConf` the `ph` crate actually wants.
+    // Shared with `PerfectMapWithKeys::new`, which has no fingerprinting/values concerns
+    // of its own but still needs to build a `GOFunction` with the same defaults.
+    fn to_build_conf(&self) -> GOBuildConf {
+        let mut conf = GOConf::default();
+        if let Some(bits_per_seed) = self.bits_per_seed {
+            conf.bits_per_seed = bits_per_seed;
+        }
+
+        let mut build_conf = GOBuildConf::with_lsize(conf, self.relative_level_size);
+        #[cfg(feature = "rayon")]
+        {
+            build_conf.use_multiple_threads = self.use_multiple_threads;
+        }
+
+        build_conf
+    }
+
+    pub fn build<K: Hash + Sync, V, U: Into<V>>(self, keys: &[K], values: Vec<U>) -> PerfectMap<K, V> {
+        let build_conf = self.to_build_conf();
+        PerfectMap::build(keys, values, build_conf, self.fingerprint_bits)
+    }
+}
+
+// Magic tag + format version + hash function version + slot count, prepended by `to_bytes`
+// to the length-prefixed hash function bytes and the bincode-encoded values/fingerprints,
+// and checked by `from_bytes`. Lets `from_bytes` reject
+// foreign, truncated, or incompatible data up front instead of handing attacker-controlled
+// bytes straight to `GOFunction::read` and finding out the hard way.
+const CONTAINER_MAGIC: [u8; 4] = *b"PMC1";
+const CONTAINER_FORMAT_VERSION: u8 = 1;
+const FUNCTION_FORMAT_VERSION: u8 = 1;
+const CONTAINER_HEADER_LEN: usize = 4 + 1 + 1 + 8;
+
+#[derive(Debug)]
+pub enum ContainerError {
+    BadMagic,
+    UnsupportedFormatVersion(u8),
+    UnsupportedFunctionVersion(u8),
+    SlotCountMismatch { header: u64, values: usize },
+    Truncated,
+    Corrupt,
+}
+
+impl core::fmt::Display for ContainerError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            ContainerError::BadMagic => write!(f, "not a PerfectMap container (bad magic)"),
+            ContainerError::UnsupportedFormatVersion(v) => write!(f, "unsupported container format version {v}"),
+            ContainerError::UnsupportedFunctionVersion(v) => write!(f, "unsupported hash function version {v}"),
+            ContainerError::SlotCountMismatch { header, values } => {
+                write!(f, "container header declares {header} slots but its contents hold {values}")
+            }
+            ContainerError::Truncated => write!(f, "truncated container"),
+            ContainerError::Corrupt => write!(f, "corrupt container"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ContainerError {}
+
+#[cfg(feature = "serde")]
+impl<K, V: serde::Serialize> PerfectMap<K, V> {
+    // Compact, versioned binary representation: a small header (magic, format version,
+    // hash function version, slot count), followed by the hash function (itself
+    // length-prefixed so `from_bytes` can carve it out and validate it on its own before
+    // touching anything else), followed by the values and fingerprints, bincode-encoded.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ContainerError> {
+        let function_bytes = write_function_bytes(&self.function).map_err(|_| ContainerError::Corrupt)?;
+
+        let mut out = Vec::with_capacity(CONTAINER_HEADER_LEN + 8 + function_bytes.len());
+        out.extend_from_slice(&CONTAINER_MAGIC);
+        out.push(CONTAINER_FORMAT_VERSION);
+        out.push(FUNCTION_FORMAT_VERSION);
+        out.extend_from_slice(&(self.values.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(function_bytes.len() as u64).to_le_bytes());
+        out.extend_from_slice(&function_bytes);
+
+        let fingerprints_wire = self.fingerprints.as_ref().map(Fingerprints::as_wire);
+        bincode::serialize_into(&mut out, &(&self.values, &fingerprints_wire)).map_err(|_| ContainerError::Corrupt)?;
+        Ok(out)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, K, V: serde::Deserialize<'de>> PerfectMap<K, V> {
+    // The counterpart to `to_bytes`. Order matters here: the header is validated first,
+    // then the hash function is carved out by its own length prefix and read/validated on
+    // its own, and only once the header's declared slot count has been checked against the
+    // function's own idea of how many slots it was built for do we bincode-deserialize the
+    // (values, fingerprints) tail — which is the part whose size is otherwise fully
+    // attacker-controlled. This avoids handing `GOFunction::read`, or a since-unbounded
+    // `Vec<V>` length prefix, unvalidated bytes before we've established the data is at
+    // least internally consistent.
+    pub fn from_bytes(bytes: &'de [u8]) -> Result<Self, ContainerError> {
+        if bytes.len() < CONTAINER_HEADER_LEN {
+            return Err(ContainerError::Truncated);
+        }
+
+        let (header, rest) = bytes.split_at(CONTAINER_HEADER_LEN);
+
+        if header[0..4] != CONTAINER_MAGIC {
+            return Err(ContainerError::BadMagic);
+        }
+
+        let format_version = header[4];
+        if format_version != CONTAINER_FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedFormatVersion(format_version));
+        }
+
+        let function_version = header[5];
+        if function_version != FUNCTION_FORMAT_VERSION {
+            return Err(ContainerError::UnsupportedFunctionVersion(function_version));
+        }
+
+        let mut slot_count_bytes = [0u8; 8];
+        slot_count_bytes.copy_from_slice(&header[6..14]);
+        let slot_count = u64::from_le_bytes(slot_count_bytes);
+
+        if rest.len() < 8 {
+            return Err(ContainerError::Truncated);
+        }
+        let (function_len_bytes, rest) = rest.split_at(8);
+        let mut function_len_buf = [0u8; 8];
+        function_len_buf.copy_from_slice(function_len_bytes);
+        let function_len = u64::from_le_bytes(function_len_buf) as usize;
+
+        if rest.len() < function_len {
+            return Err(ContainerError::Truncated);
+        }
+        let (function_bytes, tail) = rest.split_at(function_len);
+
+        let function = read_function_bytes(function_bytes).map_err(|_| ContainerError::Corrupt)?;
+
+        if function.len() as u64 != slot_count {
+            return Err(ContainerError::SlotCountMismatch { header: slot_count, values: function.len() });
+        }
+
+        let (values, fingerprints_wire): (Vec<V>, Option<(u8, serde_bytes::ByteBuf)>) =
+            bincode::deserialize(tail).map_err(|_| ContainerError::Corrupt)?;
+        let fingerprints = fingerprints_wire.map(|(bits, bytes)| Fingerprints::from_wire(bits, bytes));
+
+        if values.len() as u64 != slot_count {
+            return Err(ContainerError::SlotCountMismatch { header: slot_count, values: values.len() });
+        }
+
+        Ok(PerfectMap { function, values, fingerprints, spooky: PhantomData })
     }
 }
 
@@ -64,12 +429,13 @@ impl<K, V: serde::Serialize> serde::Serialize for PerfectMap<K,V> {
         S: serde::Serializer {
         use serde::ser::{SerializeStruct, Error};
 
-        let mut state = serializer.serialize_struct("PerfectMap", 2)?;
+        let mut state = serializer.serialize_struct("PerfectMap", 3)?;
         state.serialize_field("values", &self.values)?;
 
-        let mut hasher_bytes = Vec::with_capacity(self.function.write_bytes());
-        self.function.write(&mut hasher_bytes).map_err(|_| S::Error::custom("couldn't write hash function"))?; 
+        let hasher_bytes = write_function_bytes(&self.function).map_err(|_| S::Error::custom("couldn't write hash function"))?;
         state.serialize_field("function", &serde_bytes::ByteBuf::from(hasher_bytes))?;
+
+        state.serialize_field("fingerprints", &self.fingerprints.as_ref().map(Fingerprints::as_wire))?;
         state.end()
     }
 }
@@ -80,7 +446,10 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de> {
+        #[cfg(feature = "std")]
         use std::borrow::Cow;
+        #[cfg(not(feature = "std"))]
+        use alloc::borrow::Cow;
 
         #[repr(transparent)]
         struct CowBytes<'de>(Cow<'de, [u8]>);
@@ -107,7 +476,7 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
         
         #[derive(serde::Deserialize)]
         #[serde(field_identifier, rename_all = "lowercase")]
-        enum Field { Values, Function }
+        enum Field { Values, Function, Fingerprints }
 
         #[repr(transparent)]
         struct PerfectMapVisitor<K,V> {
@@ -118,7 +487,7 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
         impl<'de, K, V: serde::Deserialize<'de>> serde::de::Visitor<'de> for PerfectMapVisitor<K, V> {
             type Value = PerfectMap<K, V>;
 
-            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
                 formatter.write_str("struct PerfectMap")
             }
 
@@ -127,10 +496,12 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
                     A: serde::de::SeqAccess<'de>, {
                 let values: Vec<V> = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
                 let function_bytes: &[u8] = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
-                
-                let function = GOFunction::read(&mut function_bytes.as_ref()).map_err(|_| serde::de::Error::custom("invalid bytes: expected bytes representing a ph::GOFunction"))?;
+                let fingerprints: Option<(u8, serde_bytes::ByteBuf)> = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(2, &self))?;
 
-                Ok(PerfectMap { function, values, spooky: PhantomData })
+                let function = read_function_bytes(function_bytes.as_ref()).map_err(|_| serde::de::Error::custom("invalid bytes: expected bytes representing a ph::GOFunction"))?;
+                let fingerprints = fingerprints.map(|(bits, bytes)| Fingerprints::from_wire(bits, bytes));
+
+                Ok(PerfectMap { function, values, fingerprints, spooky: PhantomData })
             }
             
             fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
@@ -138,6 +509,7 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
                     A: serde::de::MapAccess<'de>, {
                 let mut values: Option<Vec<V>> = None;
                 let mut function_bytes: Option<Cow<'de, [u8]>> = None;
+                let mut fingerprints: Option<Option<(u8, serde_bytes::ByteBuf)>> = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -149,25 +521,236 @@ impl<'de, K, V: serde::Deserialize<'de>> serde::Deserialize<'de> for PerfectMap<
                         Field::Values => {
                             if values.is_some() { return Err(serde::de::Error::duplicate_field("function")) };
                             values = Some(map.next_value()?);
+                        },
+                        Field::Fingerprints => {
+                            if fingerprints.is_some() { return Err(serde::de::Error::duplicate_field("fingerprints")) };
+                            fingerprints = Some(map.next_value()?);
                         }
                     }
                 }
-                
+
                 let function_bytes = function_bytes.ok_or_else(|| serde::de::Error::missing_field("function"))?;
                 let values = values.ok_or_else(|| serde::de::Error::missing_field("values"))?;
+                let fingerprints = fingerprints.ok_or_else(|| serde::de::Error::missing_field("fingerprints"))?;
 
-                let function = GOFunction::read(&mut function_bytes.as_ref()).map_err(|_| serde::de::Error::custom("invalid bytes: expected bytes representing a ph::GOFunction"))?;
-
+                let function = read_function_bytes(function_bytes.as_ref()).map_err(|_| serde::de::Error::custom("invalid bytes: expected bytes representing a ph::GOFunction"))?;
+                let fingerprints = fingerprints.map(|(bits, bytes)| Fingerprints::from_wire(bits, bytes));
 
-                Ok(PerfectMap { function, values, spooky: PhantomData })
+                Ok(PerfectMap { function, values, fingerprints, spooky: PhantomData })
             }
         }
         
-        const FIELDS: &'static [&'static str] = &["values", "function"];
+        const FIELDS: &'static [&'static str] = &["values", "function", "fingerprints"];
         deserializer.deserialize_struct("PerfectMap", FIELDS, PerfectMapVisitor { spooky: PhantomData })
     }
 }
 
+// Like `PerfectMap`, but also retains each key at its assigned slot. The extra
+// `Vec<K>` costs memory the plain map avoids, but in exchange makes `get`/`contains_key`
+// exact (no probabilistic fingerprint needed) and enables `len`/`iter`/`keys`/`values`,
+// which `PerfectMap` can't offer since the MPHF alone can't recover what was inserted.
+pub struct PerfectMapWithKeys<K, V> {
+    function: GOFunction,
+    keys: Vec<K>,
+    values: Vec<V>,
+}
+
+impl<K: Hash + Sync, V> PerfectMapWithKeys<K, V> {
+    pub fn new<U: Into<V>>(keys: Vec<K>, values: Vec<U>) -> PerfectMapWithKeys<K, V> {
+        assert!(keys.len() == values.len());
+
+        let hasher = GOFunction::from_slice_with_conf(&keys, PerfectMapBuilder::default().to_build_conf());
+
+        let map_len = values.len();
+        let mut reordered_keys = Vec::with_capacity(map_len);
+        let mut reordered_vals = Vec::with_capacity(map_len);
+
+        for (k, v) in keys.into_iter().zip(values.into_iter()) {
+            let new_idx = hasher.get(&k).unwrap() as usize;
+            reordered_keys.spare_capacity_mut()[new_idx].write(k);
+            reordered_vals.spare_capacity_mut()[new_idx].write(v.into());
+        }
+
+        unsafe {
+            reordered_keys.set_len(map_len);
+            reordered_vals.set_len(map_len);
+        }
+
+        PerfectMapWithKeys {
+            function: hasher,
+            keys: reordered_keys,
+            values: reordered_vals,
+        }
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let slot = self.function.get(key)? as usize;
+
+        if Borrow::<Q>::borrow(self.keys.get(slot)?) == key {
+            self.values.get(slot)
+        } else {
+            None
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.keys.iter()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.values.iter()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.keys.iter().zip(self.values.iter())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<K: serde::Serialize, V: serde::Serialize> serde::Serialize for PerfectMapWithKeys<K, V> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer {
+        use serde::ser::{SerializeStruct, Error};
+
+        let mut state = serializer.serialize_struct("PerfectMapWithKeys", 3)?;
+        state.serialize_field("keys", &self.keys)?;
+        state.serialize_field("values", &self.values)?;
+
+        let hasher_bytes = write_function_bytes(&self.function).map_err(|_| S::Error::custom("couldn't write hash function"))?;
+        state.serialize_field("function", &serde_bytes::ByteBuf::from(hasher_bytes))?;
+        state.end()
+    }
+}
+
+// After reconstructing the `GOFunction`, each retained key is re-hashed to confirm it
+// still lands on the slot it was serialized at. A mismatch means the `ph` on-disk format
+// changed (or the bytes are stale/corrupt) underneath us, so rather than serve a broken
+// map we rebuild the MPHF from the retained keys/values.
+#[cfg(feature = "serde")]
+impl<'de, K, V> serde::Deserialize<'de> for PerfectMapWithKeys<K, V>
+where
+    K: serde::Deserialize<'de> + Hash + Sync,
+    V: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de> {
+        #[cfg(feature = "std")]
+        use std::borrow::Cow;
+        #[cfg(not(feature = "std"))]
+        use alloc::borrow::Cow;
+
+        #[repr(transparent)]
+        struct CowBytes<'de>(Cow<'de, [u8]>);
+
+        impl<'de> serde::Deserialize<'de> for CowBytes<'de> {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de> {
+                if deserializer.is_human_readable() {
+                    Vec::<u8>::deserialize(deserializer).map(|v| CowBytes(Cow::Owned(v)))
+                } else {
+                    <&[u8]>::deserialize(deserializer).map(|v| CowBytes(Cow::Borrowed(v)))
+                }
+            }
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(field_identifier, rename_all = "lowercase")]
+        enum Field { Keys, Values, Function }
+
+        #[repr(transparent)]
+        struct PerfectMapWithKeysVisitor<K, V> {
+            spooky: PhantomData<(K, V)>,
+        }
+
+        impl<'de, K: serde::Deserialize<'de> + Hash + Sync, V: serde::Deserialize<'de>> serde::de::Visitor<'de> for PerfectMapWithKeysVisitor<K, V> {
+            type Value = PerfectMapWithKeys<K, V>;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("struct PerfectMapWithKeys")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::SeqAccess<'de>, {
+                let keys: Vec<K> = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
+                let values: Vec<V> = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
+                let function_bytes: &[u8] = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(2, &self))?;
+
+                Self::finish(keys, values, function_bytes)
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>, {
+                let mut keys: Option<Vec<K>> = None;
+                let mut values: Option<Vec<V>> = None;
+                let mut function_bytes: Option<Cow<'de, [u8]>> = None;
+
+                while let Some(key) = map.next_key()? {
+                    match key {
+                        Field::Keys => {
+                            if keys.is_some() { return Err(serde::de::Error::duplicate_field("keys")) };
+                            keys = Some(map.next_value()?);
+                        },
+                        Field::Function => {
+                            if function_bytes.is_some() { return Err(serde::de::Error::duplicate_field("function")) };
+                            function_bytes = Some(map.next_value()?);
+                        },
+                        Field::Values => {
+                            if values.is_some() { return Err(serde::de::Error::duplicate_field("values")) };
+                            values = Some(map.next_value()?);
+                        }
+                    }
+                }
+
+                let keys = keys.ok_or_else(|| serde::de::Error::missing_field("keys"))?;
+                let values = values.ok_or_else(|| serde::de::Error::missing_field("values"))?;
+                let function_bytes = function_bytes.ok_or_else(|| serde::de::Error::missing_field("function"))?;
+
+                Self::finish(keys, values, &function_bytes)
+            }
+        }
+
+        impl<'de, K: serde::Deserialize<'de> + Hash + Sync, V: serde::Deserialize<'de>> PerfectMapWithKeysVisitor<K, V> {
+            fn finish<E: serde::de::Error>(keys: Vec<K>, values: Vec<V>, function_bytes: &[u8]) -> Result<PerfectMapWithKeys<K, V>, E> {
+                if keys.len() != values.len() {
+                    return Err(E::custom("keys and values have different lengths"));
+                }
+
+                let function = read_function_bytes(function_bytes.as_ref()).map_err(|_| E::custom("invalid bytes: expected bytes representing a ph::GOFunction"))?;
+
+                let consistent = keys.iter().enumerate().all(|(slot, key)| {
+                    function.get(key).map(|s| s as usize) == Some(slot)
+                });
+
+                if consistent {
+                    Ok(PerfectMapWithKeys { function, keys, values })
+                } else {
+                    Ok(PerfectMapWithKeys::new(keys, values))
+                }
+            }
+        }
+
+        const FIELDS: &'static [&'static str] = &["keys", "values", "function"];
+        deserializer.deserialize_struct("PerfectMapWithKeys", FIELDS, PerfectMapWithKeysVisitor { spooky: PhantomData })
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[cfg(feature = "serde")]
@@ -190,4 +773,95 @@ mod test {
         assert_eq!(deserialized_map.get("c"), Some(&3i32));
         assert_eq!(deserialized_map.get("d"), Some(&4i32));
     }
+
+    #[test]
+    fn test_fingerprint_rejects_unknown_keys() {
+        use crate::PerfectMap;
+
+        let map: PerfectMap<String, i32> = PerfectMap::new_with_fingerprint(
+            &["a".into(), "b".into(), "c".into(), "d".into()],
+            vec![1, 2, 3, 4],
+            8,
+        );
+
+        assert_eq!(map.get("a"), Some(&1i32));
+        assert_eq!(map.get("b"), Some(&2i32));
+        assert!(map.contains_key("c"));
+        assert!(!map.contains_key("nonexistent-key"));
+        assert_eq!(map.get("nonexistent-key"), None);
+    }
+
+    #[test]
+    fn test_builder_configures_fingerprint_and_lookups() {
+        use crate::{PerfectMap, PerfectMapBuilder};
+
+        let map: PerfectMap<String, i32> = PerfectMapBuilder::new()
+            .relative_level_size(200)
+            .bits_per_seed(4)
+            .fingerprint_bits(16)
+            .build(&["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()], vec![1, 2, 3, 4]);
+
+        assert_eq!(map.get("a"), Some(&1i32));
+        assert_eq!(map.get("d"), Some(&4i32));
+        assert!(map.contains_key("c"));
+        assert!(!map.contains_key("nonexistent-key"));
+    }
+
+    #[test]
+    fn test_with_keys_iteration_and_exact_membership() {
+        use crate::PerfectMapWithKeys;
+
+        let keys = vec!["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()];
+        let map: PerfectMapWithKeys<String, i32> = PerfectMapWithKeys::new(keys, vec![1, 2, 3, 4]);
+
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.get("a"), Some(&1i32));
+        assert!(map.contains_key("d"));
+        assert!(!map.contains_key("nonexistent-key"));
+
+        let mut collected: Vec<(&String, &i32)> = map.iter().collect();
+        collected.sort();
+        assert_eq!(
+            collected,
+            vec![
+                (&"a".to_string(), &1),
+                (&"b".to_string(), &2),
+                (&"c".to_string(), &3),
+                (&"d".to_string(), &4),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_to_from_bytes_roundtrip() {
+        use crate::{ContainerError, PerfectMap};
+
+        let map: PerfectMap<String, i32> = PerfectMap::new(&["a".into(), "b".into(), "c".into(), "d".into()], vec![1, 2, 3, 4]);
+
+        let bytes = map.to_bytes().unwrap();
+        let restored: PerfectMap<String, i32> = PerfectMap::from_bytes(&bytes).unwrap();
+
+        assert_eq!(restored.get("a"), Some(&1i32));
+        assert_eq!(restored.get("d"), Some(&4i32));
+
+        let mut truncated = bytes.clone();
+        truncated.truncate(2);
+        assert!(matches!(PerfectMap::<String, i32>::from_bytes(&truncated), Err(ContainerError::Truncated)));
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 0xff;
+        assert!(matches!(PerfectMap::<String, i32>::from_bytes(&bad_magic), Err(ContainerError::BadMagic)));
+
+        // Tamper with only the header's slot count, leaving the function and value bytes
+        // internally consistent with each other (4 slots) but disagreeing with the header
+        // (now claims 5). This must be caught against the function's own slot count before
+        // the values/fingerprints tail is ever deserialized.
+        let mut bad_slot_count = bytes.clone();
+        bad_slot_count[6..14].copy_from_slice(&5u64.to_le_bytes());
+        assert!(matches!(
+            PerfectMap::<String, i32>::from_bytes(&bad_slot_count),
+            Err(ContainerError::SlotCountMismatch { header: 5, values: 4 })
+        ));
+    }
 }
\ No newline at end of file